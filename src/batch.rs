@@ -0,0 +1,117 @@
+//! Batch verification for many [`crate::range::DefaultRangeCommitCircuit`]
+//! proofs issued against the same `ParamsKZG`. Verifying proofs one at a time
+//! pays the full multi-scalar-multiplication and pairing cost per proof;
+//! batching them amortizes that cost via a random-linear-combination
+//! accumulator, in place of the `SingleVerifier` that the single-proof path
+//! in `src/main.rs` uses.
+
+use halo2_proofs::{
+    plonk::{verify_proof, Error, VerifyingKey},
+    poly::kzg::{commitment::ParamsKZG, strategy::BatchVerifier},
+    transcript::{Blake2bRead, Challenge255},
+};
+use pasta_curves::pallas;
+
+/// Queues `[commitment, lower, upper]`-instance proofs for a single
+/// `RangeProofBatch::verify` call.
+#[derive(Default)]
+pub struct RangeProofBatch {
+    proofs: Vec<(Vec<u8>, Vec<Vec<pallas::Base>>)>,
+}
+
+impl RangeProofBatch {
+    pub fn new() -> Self {
+        Self { proofs: Vec::new() }
+    }
+
+    /// Queues one proof with its per-column public instance values.
+    pub fn add(&mut self, proof: Vec<u8>, instance: Vec<Vec<pallas::Base>>) {
+        self.proofs.push((proof, instance));
+    }
+
+    /// Verifies every queued proof together, accepting only if all of them
+    /// are valid -- a single bad proof fails the whole batch closed.
+    pub fn verify(
+        &self,
+        params: &ParamsKZG<pallas::Base>,
+        vk: &VerifyingKey<pallas::Base>,
+    ) -> Result<(), Error> {
+        let mut strategy = BatchVerifier::new(params);
+        for (proof, instance) in &self.proofs {
+            let mut transcript = Blake2bRead::<_, _, Challenge255<_>>::init(&proof[..]);
+            strategy = verify_proof(params, vk, strategy, &[instance], &mut transcript)?;
+        }
+
+        if strategy.finalize() {
+            Ok(())
+        } else {
+            Err(Error::ConstraintSystemFailure)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::range::DefaultRangeCommitCircuit as RangeCommitCircuit;
+    use halo2_proofs::{plonk::{create_proof, keygen_pk, keygen_vk}, transcript::Blake2bWrite};
+    use crate::lookup_range_check::MIN_K;
+    use rand_core::OsRng;
+
+    // See `lookup_range_check::MIN_K` for why the domain has to be strictly
+    // larger than the lookup table it loads.
+    const K: u32 = MIN_K;
+
+    fn prove(
+        params: &ParamsKZG<pallas::Base>,
+        secret: u64,
+        lower: u64,
+        upper: u64,
+    ) -> (Vec<u8>, Vec<Vec<pallas::Base>>) {
+        // see `RangeCommitCircuit::witness` for why this, not a hand-rolled
+        // Poseidon hash, is how every prover in this crate builds its witness
+        let (circuit, instance) = RangeCommitCircuit::witness(secret, lower, upper, vec![]);
+
+        let empty = RangeCommitCircuit::default();
+        let vk = keygen_vk(params, &empty).unwrap();
+        let pk = keygen_pk(params, vk, &empty).unwrap();
+
+        let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
+        create_proof(params, &pk, &[circuit], &[&instance], OsRng, &mut transcript).unwrap();
+        (transcript.finalize(), instance)
+    }
+
+    #[test]
+    fn accepts_a_batch_of_valid_proofs() {
+        // Every `prove`/`verify` call below must share this one `ParamsKZG`:
+        // `ParamsKZG::new` samples a fresh trusted-setup secret each time, so
+        // independently-constructed params never agree with each other.
+        let params: ParamsKZG<pallas::Base> = ParamsKZG::new(K);
+        let empty = RangeCommitCircuit::default();
+        let vk = keygen_vk(&params, &empty).unwrap();
+
+        let mut batch = RangeProofBatch::new();
+        let (proof_a, instance_a) = prove(&params, 27, 18, 65);
+        let (proof_b, instance_b) = prove(&params, 40, 18, 65);
+        batch.add(proof_a, instance_a);
+        batch.add(proof_b, instance_b);
+
+        assert!(batch.verify(&params, &vk).is_ok());
+    }
+
+    #[test]
+    fn rejects_a_batch_with_one_out_of_range_proof() {
+        let params: ParamsKZG<pallas::Base> = ParamsKZG::new(K);
+        let empty = RangeCommitCircuit::default();
+        let vk = keygen_vk(&params, &empty).unwrap();
+
+        let mut batch = RangeProofBatch::new();
+        let (valid_proof, valid_instance) = prove(&params, 27, 18, 65);
+        // secret = 99 is outside [18, 65]: this proof should not verify
+        let (bad_proof, bad_instance) = prove(&params, 99, 18, 65);
+        batch.add(valid_proof, valid_instance);
+        batch.add(bad_proof, bad_instance);
+
+        assert!(batch.verify(&params, &vk).is_err());
+    }
+}
@@ -0,0 +1,3 @@
+pub mod range;
+pub mod lookup_range_check;
+pub mod batch;
@@ -0,0 +1,313 @@
+//! A lookup-based range-check gadget, analogous to the `LookupRangeCheckConfig`
+//! used in the Orchard circuit. Proves that a field element lies in `[0, 2^n)`
+//! in roughly `n / K` rows instead of `O(n)`.
+//!
+//! The value is decomposed into a little-endian running sum of `K`-bit
+//! windows: `z_0 = v`, `z_{i+1} = (z_i - w_i) * 2^-K`, so that `w_i` is the
+//! `i`-th `K`-bit window of `v` and `z_last == 0` once every window has been
+//! consumed. Each `w_i` is constrained to appear in a fixed column preloaded
+//! with every value in `[0, 2^K)`, which forces `0 <= w_i < 2^K`. A final
+//! (possibly short) window is range-checked against the same table by
+//! scaling it up to the full `K` bits.
+//!
+//! The `parallel` feature below (see `windows`/`window_at`) is this crate's
+//! parallel-synthesis work: it parallelizes computing the per-window
+//! witness *values* across a rayon pool ahead of assignment. It stops short
+//! of parallelizing the region *writes* themselves -- `Region` in
+//! halo2_proofs is a single `&mut` borrow, with no supported way to split
+//! an in-progress region assignment across threads in safe Rust. At this
+//! crate's window counts (7-13 for `N_BITS` 64/128) parallelizing a handful
+//! of bit-shift ops is unlikely to beat the serial loop it replaces; treat
+//! `parallel` as a knob for larger `N_BITS`, not a proven win today.
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Selector, TableColumn},
+    poly::Rotation,
+};
+
+/// Bit-width of a single lookup window.
+pub const K: usize = 10;
+
+/// Minimum PLONK domain size (the `k` passed to `ParamsKZG::new` or
+/// `MockProver::run`) for any circuit that loads this chip's table.
+///
+/// The table alone preloads `2^K` rows, so `k` must be strictly larger than
+/// `K` to leave room for whatever else the circuit assigns on top of it --
+/// the Poseidon hash, diff gates, running-sum windows, blinding rows, and so
+/// on. Callers should use this constant (or something derived from it)
+/// rather than hardcoding a domain size next to a comment re-deriving the
+/// same reasoning.
+pub const MIN_K: u32 = K as u32 + 1;
+
+/// The `z_i` cells produced while range-checking a value window by window.
+/// `zs[0]` is the original value and `zs.last()` is constrained to zero.
+pub type RunningSum<F> = Vec<AssignedCell<F, F>>;
+
+/// Returns the `len`-bit subset of `value` starting at bit `start`,
+/// little-endian.
+fn bitrange_subset<F: FieldExt>(value: F, start: usize, len: usize) -> F {
+    let repr = value.to_repr();
+    let bytes = repr.as_ref();
+    let bit = |i: usize| (bytes[i / 8] >> (i % 8)) & 1 == 1;
+
+    let mut acc = F::zero();
+    for i in (0..len).rev() {
+        acc = acc.double();
+        if bit(start + i) {
+            acc += F::one();
+        }
+    }
+    acc
+}
+
+#[derive(Clone, Debug)]
+pub struct LookupRangeCheckConfig<F: FieldExt> {
+    q_lookup: Selector,
+    q_last: Selector,
+    running_sum: Column<Advice>,
+    shift: Column<Fixed>,
+    table_idx: TableColumn,
+    _marker: std::marker::PhantomData<F>,
+}
+
+impl<F: FieldExt> LookupRangeCheckConfig<F> {
+    /// The advice column the running sum is assigned into. Exposed so a
+    /// circuit can tie other gates (e.g. "this is `a - b`") to `z_0`.
+    pub fn running_sum_column(&self) -> Column<Advice> {
+        self.running_sum
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>, running_sum: Column<Advice>) -> Self {
+        meta.enable_equality(running_sum);
+
+        let q_lookup = meta.complex_selector();
+        let q_last = meta.selector();
+        let shift = meta.fixed_column();
+        let table_idx = meta.lookup_table_column();
+
+        meta.lookup("window fits in [0, 2^K)", |meta| {
+            let q_lookup = meta.query_selector(q_lookup);
+            let z_cur = meta.query_advice(running_sum, Rotation::cur());
+            let z_next = meta.query_advice(running_sum, Rotation::next());
+
+            let word = z_cur - z_next * F::from(1u64 << K);
+            vec![(q_lookup * word, table_idx)]
+        });
+
+        meta.lookup("scaled window fits in [0, 2^K)", |meta| {
+            let q_lookup = meta.query_selector(q_lookup);
+            let z_cur = meta.query_advice(running_sum, Rotation::cur());
+            let z_next = meta.query_advice(running_sum, Rotation::next());
+            let shift = meta.query_fixed(shift, Rotation::cur());
+
+            // `word` is the K-bit window at this row, already bound to
+            // `[0, 2^K)` by the lookup above; `shift` is 1 for a full
+            // window and `2^(K - r)` for a short final window of `r` bits,
+            // so the scaled word still lands inside the table iff the
+            // window itself is `< 2^r`.
+            let word = z_cur - z_next * F::from(1u64 << K);
+            vec![(q_lookup * word * shift, table_idx)]
+        });
+
+        meta.create_gate("last window is zero", |meta| {
+            let q_last = meta.query_selector(q_last);
+            let z_last = meta.query_advice(running_sum, Rotation::cur());
+            vec![q_last * z_last]
+        });
+
+        Self {
+            q_lookup,
+            q_last,
+            running_sum,
+            shift,
+            table_idx,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    /// Loads the fixed table with every value in `[0, 2^K)`. Call once per
+    /// circuit; any number of `range_check` calls can share it.
+    pub fn load_table(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        layouter.assign_table(
+            || "K-bit lookup table",
+            |mut table| {
+                for index in 0..(1 << K) {
+                    table.assign_cell(
+                        || "table_idx",
+                        self.table_idx,
+                        index,
+                        || Value::known(F::from(index as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Range-checks `value` to `num_bits` bits, returning the running-sum
+    /// cells `[z_0, z_1, ..., z_last]` with `z_0` copy-equal to `value`.
+    pub fn range_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<RunningSum<F>, Error> {
+        layouter.assign_region(
+            || "lookup range check",
+            |mut region| self.assign(&mut region, &value, num_bits),
+        )
+    }
+
+    fn assign(
+        &self,
+        region: &mut Region<'_, F>,
+        value: &AssignedCell<F, F>,
+        num_bits: usize,
+    ) -> Result<RunningSum<F>, Error> {
+        let num_windows = (num_bits + K - 1) / K;
+        let windows = Self::windows(value.value().copied(), num_bits, num_windows);
+
+        let mut zs = vec![value.copy_advice(|| "z_0", region, self.running_sum, 0)?];
+        for (i, (z_next, shift_val)) in windows.into_iter().enumerate() {
+            self.q_lookup.enable(region, i)?;
+            region.assign_fixed(|| "shift", self.shift, i, || Value::known(shift_val))?;
+
+            let z_next_cell =
+                region.assign_advice(|| format!("z_{}", i + 1), self.running_sum, i + 1, || z_next)?;
+            zs.push(z_next_cell);
+        }
+
+        self.q_last.enable(region, num_windows)?;
+        Ok(zs)
+    }
+
+    /// Computes `(z_{i+1}, shift_i)` for window `i`. Because `z_{i+1}` is
+    /// just `bitrange_subset(v, (i+1)*K, ..)`, every window can be derived
+    /// straight from the original value `v` -- none of them depend on a
+    /// neighbour's result, which is what makes them safe to compute
+    /// concurrently under the `parallel` feature.
+    fn window_at(v: Value<F>, num_bits: usize, i: usize) -> (Value<F>, F) {
+        let bits_this_window = core::cmp::min(K, num_bits - i * K);
+        let shift_val = F::from(1u64 << (K - bits_this_window));
+
+        let remaining_bits = num_bits.saturating_sub((i + 1) * K);
+        let z_next = v.map(|v| bitrange_subset(v, (i + 1) * K, remaining_bits));
+
+        (z_next, shift_val)
+    }
+
+    /// Opt-in parallel witness precomputation: the per-window values above
+    /// are embarrassingly parallel, so with `--features parallel` they're
+    /// computed across a rayon thread pool instead of one at a time. This
+    /// does not parallelize the region assignment in `assign` -- only the
+    /// values it later assigns sequentially. The serial fallback below
+    /// keeps the exact same per-window computation.
+    #[cfg(feature = "parallel")]
+    fn windows(v: Value<F>, num_bits: usize, num_windows: usize) -> Vec<(Value<F>, F)>
+    where
+        F: Send + Sync,
+    {
+        use rayon::prelude::*;
+        (0..num_windows)
+            .into_par_iter()
+            .map(|i| Self::window_at(v, num_bits, i))
+            .collect()
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn windows(v: Value<F>, num_bits: usize, num_windows: usize) -> Vec<(Value<F>, F)> {
+        (0..num_windows)
+            .map(|i| Self::window_at(v, num_bits, i))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use halo2_proofs::{
+        circuit::SimpleFloorPlanner,
+        dev::MockProver,
+        plonk::Circuit,
+    };
+    use pasta_curves::pallas::Base as Fp;
+
+    #[derive(Default)]
+    struct TestCircuit {
+        value: Value<Fp>,
+        num_bits: usize,
+    }
+
+    impl Circuit<Fp> for TestCircuit {
+        type Config = (Column<Advice>, LookupRangeCheckConfig<Fp>);
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self { value: Value::unknown(), num_bits: self.num_bits }
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+            let value_col = meta.advice_column();
+            meta.enable_equality(value_col);
+            let running_sum = meta.advice_column();
+            (value_col, LookupRangeCheckConfig::configure(meta, running_sum))
+        }
+
+        fn synthesize(
+            &self,
+            (value_col, config): Self::Config,
+            mut layouter: impl Layouter<Fp>,
+        ) -> Result<(), Error> {
+            config.load_table(&mut layouter)?;
+            let value = layouter.assign_region(
+                || "witness value",
+                |mut region| region.assign_advice(|| "value", value_col, 0, || self.value),
+            )?;
+            config.range_check(layouter.namespace(|| "range check"), value, self.num_bits)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn windows_matches_independent_bit_shifts() {
+        let raw: u64 = 0x1234_5678;
+        let value = Fp::from(raw);
+        let num_bits = 32;
+        let num_windows = (num_bits + K - 1) / K;
+
+        // `windows` dispatches to the rayon path under `--features parallel`
+        // and to a plain loop over `window_at` otherwise. Comparing it
+        // against `window_at` called directly would only prove the two
+        // call the same function, so instead check it against windows
+        // derived straight from `raw` with u64 shifts/masks -- independent
+        // of both `window_at` and `bitrange_subset`. That makes this a real
+        // correctness check under either feature set; run with
+        // `--features parallel` as well to additionally exercise the rayon
+        // dispatch itself, since this crate has no CI wiring both feature
+        // sets automatically.
+        let expected: Vec<(Option<Fp>, Fp)> = (0..num_windows)
+            .map(|i| {
+                let bits_this_window = core::cmp::min(K, num_bits - i * K);
+                let shift_val = Fp::from(1u64 << (K - bits_this_window));
+
+                let remaining_bits = num_bits.saturating_sub((i + 1) * K);
+                let mask = if remaining_bits == 0 { 0 } else { (1u64 << remaining_bits) - 1 };
+                let z_next = (raw >> ((i + 1) * K)) & mask;
+
+                (Some(Fp::from(z_next)), shift_val)
+            })
+            .collect();
+
+        let dispatched = LookupRangeCheckConfig::<Fp>::windows(Value::known(value), num_bits, num_windows);
+        let extract = |vs: &[(Value<Fp>, Fp)]| -> Vec<(Option<Fp>, Fp)> {
+            vs.iter().map(|(z, s)| ((*z).into_option(), *s)).collect()
+        };
+        assert_eq!(extract(&dispatched), expected);
+
+        let circuit = TestCircuit { value: Value::known(value), num_bits };
+        let prover = MockProver::run(MIN_K, &circuit, vec![]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+    }
+}
@@ -1,5 +1,3 @@
-mod range;
-
 use halo2_proofs::{
     plonk::{create_proof, keygen_pk, keygen_vk, verify_proof},
     poly::kzg::{commitment::ParamsKZG, strategy::SingleVerifier},
@@ -7,11 +5,14 @@ use halo2_proofs::{
 };
 use rand_core::OsRng;
 use pasta_curves::pallas;
-use range::RangeCommitCircuit;
+use halo2_range_proof::lookup_range_check::MIN_K;
+use halo2_range_proof::range::DefaultRangeCommitCircuit as RangeCommitCircuit;
 
 fn main() {
     // ---------------- public parameters -----------------
-    let k = 8;                                        // 2^8 rows
+    // See `lookup_range_check::MIN_K` for why the domain has to be strictly
+    // larger than the lookup table it loads.
+    let k = MIN_K;
     let params: ParamsKZG<pallas::Base> = ParamsKZG::new(k);
 
     // ---------------- public inputs ---------------------
@@ -19,26 +20,15 @@ fn main() {
     let upper  = 65u64;
     let secret = 27u64;
 
-    let commitment = pallas::Base::from(secret);
-
     // ------------------- keys ---------------------------
     let empty  = RangeCommitCircuit::default();
     let vk     = keygen_vk(&params, &empty).unwrap();
     let pk     = keygen_pk(&params, vk, &empty).unwrap();
 
     // ------------------- witness ------------------------
-    let circuit = RangeCommitCircuit {
-        secret: Some(pallas::Base::from(secret)),
-        lower:  pallas::Base::from(lower),
-        upper:  pallas::Base::from(upper),
-    };
-
-    // instance column layout: [commit, lower, upper]
-    let instance = vec![vec![
-        commitment,
-        pallas::Base::from(lower),
-        pallas::Base::from(upper),
-    ]];
+    // see `RangeCommitCircuit::witness` for why this, not a hand-rolled
+    // Poseidon hash, is how every prover in this crate builds its witness
+    let (circuit, instance) = RangeCommitCircuit::witness(secret, lower, upper, vec![]);
 
     // ----------------- create proof ---------------------
     let mut transcript = Blake2bWrite::<_, _, Challenge255<_>>::init(vec![]);
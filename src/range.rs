@@ -1,51 +1,166 @@
+use std::marker::PhantomData;
+
+use group::ff::Field;
 use halo2_proofs::{
-    arithmetic::FieldExt,
-    circuit::{Layouter, SimpleFloorPlanner, Value},
-    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
+    poly::Rotation,
 };
-use halo2_gadgets::{
-    poseidon::{primitives::P128Pow5T3, PoseidonChip, PoseidonConfig},
-    less_than::{LtChip, LtConfig},
+use halo2_gadgets::poseidon::{
+    primitives::{ConstantLength, Hash as PoseidonHash, Spec},
+    PoseidonChip, PoseidonConfig,
 };
 use pasta_curves::pallas::Base as Fp;
+use rand_core::OsRng;
 
-/// How many bits do we allow for the secret? (<= 252 in circom example)
-const N_BITS: usize = 64;     // fits money amounts, age, etc.
+use crate::lookup_range_check::LookupRangeCheckConfig;
 
 #[derive(Clone)]
-struct RangeCommitConfig {
+struct RangeCommitConfig<const WIDTH: usize, const RATE: usize> {
     // one instance column holds [commitment, lower, upper]
     instance: Column<Instance>,
-    // Poseidon and < gadgets live in their own configs
-    poseidon: PoseidonConfig<3, 2>,
-    lt: LtConfig<N_BITS>,
+    // Poseidon lives in its own config
+    poseidon: PoseidonConfig<WIDTH, RATE>,
+    // the two bound checks are lowered to range checks on the differences
+    // `secret - lower` and `upper - secret`
+    range_check: LookupRangeCheckConfig<Fp>,
+    // witnesses `a`, `b` and `a - b` for each bound check; `diff_out` is
+    // then copied into `range_check` as the value being range-checked
+    diff_a: Column<Advice>,
+    diff_b: Column<Advice>,
+    diff_out: Column<Advice>,
+    s_diff: Selector,
+}
+
+/// Proves `lower <= secret <= upper` and binds `secret` to a hiding Poseidon
+/// commitment `H(secret, r, ..extra)` of arity `L`, using the sponge
+/// `S` instantiated at `WIDTH`/`RATE`.
+///
+/// The bound is non-strict: both sides are lowered to range-checking
+/// `secret - lower` and `upper - secret` to `[0, 2^N)`, which accepts `0`,
+/// i.e. `secret == lower` or `secret == upper`. That's an intentional
+/// relaxation from the old two-sided `LtChip` (which was strict) -- it
+/// matches how bounded attributes like age or amount limits are usually
+/// phrased ("at least 18", "at most the cap") and keeps the range-check
+/// lowering simple. Callers that need strict bounds should pass
+/// `lower + 1` / `upper - 1` themselves.
+///
+/// `L` must satisfy `2 <= L <= RATE`: the first two inputs are always
+/// `secret` and `r`, and the gadget absorbs all `L` inputs in a single
+/// permutation, the same way the rest of this circuit issues one Poseidon
+/// call. Hashing more inputs than the rate allows would need multiple
+/// absorb/squeeze rounds, which this circuit doesn't need yet.
+pub struct RangeCommitCircuit<S, const WIDTH: usize, const RATE: usize, const L: usize, const N: usize>
+where
+    S: Spec<Fp, WIDTH, RATE> + Clone,
+{
+    pub secret: Option<Fp>,  // private witness
+    pub r: Option<Fp>,       // private blinding trapdoor
+    /// Further values hashed alongside `secret` and `r`, e.g. other bounded
+    /// attributes of the same subject. Must have length `L - 2`.
+    pub extra: Vec<Fp>,
+    pub lower: Fp,           // public
+    pub upper: Fp,           // public
+    _spec: PhantomData<S>,
 }
 
-#[derive(Default)]
-pub struct RangeCommitCircuit {
-    pub secret:  Option<Fp>,   // private witness
-    pub lower:   Fp,           // public
-    pub upper:   Fp,           // public
+impl<S, const WIDTH: usize, const RATE: usize, const L: usize, const N: usize> Default
+    for RangeCommitCircuit<S, WIDTH, RATE, L, N>
+where
+    S: Spec<Fp, WIDTH, RATE> + Clone,
+{
+    fn default() -> Self {
+        Self {
+            secret: None,
+            r: None,
+            extra: Vec::new(),
+            lower: Fp::default(),
+            upper: Fp::default(),
+            _spec: PhantomData,
+        }
+    }
+}
+
+impl<S, const WIDTH: usize, const RATE: usize, const L: usize, const N: usize> Clone
+    for RangeCommitCircuit<S, WIDTH, RATE, L, N>
+where
+    S: Spec<Fp, WIDTH, RATE> + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            secret: self.secret,
+            r: self.r,
+            extra: self.extra.clone(),
+            lower: self.lower,
+            upper: self.upper,
+            _spec: PhantomData,
+        }
+    }
 }
 
-impl Circuit<Fp> for RangeCommitCircuit {
-    type Config = RangeCommitConfig;
+/// Today's defaults: `P128Pow5T3` at `WIDTH=3, RATE=2`, hashing `(secret, r)`
+/// (`L=2`), range-checking to `N_BITS=64`.
+pub type DefaultRangeCommitCircuit =
+    RangeCommitCircuit<halo2_gadgets::poseidon::primitives::P128Pow5T3, 3, 2, 2, 64>;
+
+impl<S, const WIDTH: usize, const RATE: usize, const L: usize, const N: usize> Circuit<Fp>
+    for RangeCommitCircuit<S, WIDTH, RATE, L, N>
+where
+    S: Spec<Fp, WIDTH, RATE> + Clone,
+{
+    type Config = RangeCommitConfig<WIDTH, RATE>;
     type FloorPlanner = SimpleFloorPlanner;
 
-    fn without_witnesses(&self) -> Self { Self::default() }
+    fn without_witnesses(&self) -> Self {
+        Self {
+            extra: vec![Fp::default(); self.extra.len()],
+            ..Self::default()
+        }
+    }
 
     fn configure(meta: &mut ConstraintSystem<Fp>) -> Self::Config {
+        // Force evaluation of the `2 <= L <= RATE` guard: a bad instantiation
+        // (e.g. `L = 0` or `L > RATE`) fails to compile here instead of
+        // panicking with an opaque arithmetic/index error once `synthesize`
+        // runs.
+        let _ = Self::L_IN_RANGE;
+
         // public column
         let instance = meta.instance_column();
         meta.enable_equality(instance);
 
-        // Poseidon chip needs 2 advice cols, 1 fixed, 1 selector
-        let poseidon = PoseidonChip::<Fp, P128Pow5T3, 3, 2>::configure(meta);
+        // Poseidon chip needs `RATE` advice cols, 1 fixed, 1 selector
+        let poseidon = PoseidonChip::<Fp, S, WIDTH, RATE>::configure(meta);
+
+        // Lookup range-check chip: running sum lives in its own advice col
+        let running_sum = meta.advice_column();
+        let range_check = LookupRangeCheckConfig::configure(meta, running_sum);
 
-        // Less-than chip uses one advice col
-        let lt = LtChip::<Fp, N_BITS>::configure(meta);
+        let diff_a = meta.advice_column();
+        let diff_b = meta.advice_column();
+        let diff_out = meta.advice_column();
+        meta.enable_equality(diff_a);
+        meta.enable_equality(diff_b);
+        meta.enable_equality(diff_out);
 
-        RangeCommitConfig { instance, poseidon, lt }
+        let s_diff = meta.selector();
+        meta.create_gate("a - b = diff_out", |meta| {
+            let s_diff = meta.query_selector(s_diff);
+            let a = meta.query_advice(diff_a, Rotation::cur());
+            let b = meta.query_advice(diff_b, Rotation::cur());
+            let diff = meta.query_advice(diff_out, Rotation::cur());
+            vec![s_diff * (a - b - diff)]
+        });
+
+        RangeCommitConfig {
+            instance,
+            poseidon,
+            range_check,
+            diff_a,
+            diff_b,
+            diff_out,
+            s_diff,
+        }
     }
 
     fn synthesize(
@@ -53,75 +168,168 @@ impl Circuit<Fp> for RangeCommitCircuit {
         cfg:   Self::Config,
         mut layouter: impl Layouter<Fp>
     ) -> Result<(), Error> {
+        assert_eq!(self.extra.len(), L - 2, "extra must supply exactly L - 2 values");
 
         //--------------------------------------------------------------------
-        // 1. allocate the secret number
+        // 1. allocate the secret number, its blinding trapdoor, and any
+        //    extra hashed values, one per Poseidon message column
         //--------------------------------------------------------------------
-        let secret_cell = layouter.assign_region(
-            || "load secret",
+        let (secret_cell, inputs) = layouter.assign_region(
+            || "load secret, r, extra",
             |mut region| {
-                region.assign_advice(
+                let secret = region.assign_advice(
                     || "secret",
                     cfg.poseidon.message[0],   // first advice col from Poseidon config
                     0,
                     || Value::known(self.secret.expect("secret witness missing")),
-                )
+                )?;
+                let r = region.assign_advice(
+                    || "r",
+                    cfg.poseidon.message[1],   // second advice col from Poseidon config
+                    0,
+                    || Value::known(self.r.expect("blinding trapdoor missing")),
+                )?;
+
+                let mut inputs = vec![secret.clone(), r];
+                for (i, value) in self.extra.iter().enumerate() {
+                    let cell = region.assign_advice(
+                        || format!("extra[{i}]"),
+                        cfg.poseidon.message[2 + i],
+                        0,
+                        || Value::known(*value),
+                    )?;
+                    inputs.push(cell);
+                }
+                Ok((secret, inputs))
             }
         )?;
 
         //--------------------------------------------------------------------
-        // 2. Poseidon commitment = H(secret)
+        // 2. Poseidon commitment = H(secret, r, ..extra) -- r hides the
+        //    secret so a verifier who guesses it can't confirm the guess by
+        //    recomputing the hash
         //--------------------------------------------------------------------
         let mut sponge = PoseidonChip::construct(cfg.poseidon.clone());
         let commitment = sponge.hash(
             layouter.namespace(|| "Poseidon hash"),
-            &[secret_cell.clone()]
+            &inputs,
         )?;
         // constrain to public instance[0]
         layouter.constrain_instance(commitment.cell(), cfg.instance, 0)?;
 
         //--------------------------------------------------------------------
-        // 3.  lower < secret    and    secret < upper
+        // 3.  lower <= secret    and    secret <= upper, each lowered to a
+        //     range check on the non-negative difference
         //--------------------------------------------------------------------
-        let lt_chip = LtChip::<Fp, N_BITS>::construct(cfg.lt.clone());
+        cfg.range_check.load_table(&mut layouter)?;
 
         // lower bound : public instance row 1
         let lower_cell = layouter.assign_region(
             || "load lower",
             |mut region| {
-                region.assign_advice_from_instance(
-                    || "lower",
-                    cfg.instance, 1,
-                    cfg.lt.advice, 0
-                )
-            }
+                region.assign_advice_from_instance(|| "lower", cfg.instance, 1, cfg.diff_a, 0)
+            },
         )?;
         // upper bound : public instance row 2
         let upper_cell = layouter.assign_region(
             || "load upper",
             |mut region| {
-                region.assign_advice_from_instance(
-                    || "upper",
-                    cfg.instance, 2,
-                    cfg.lt.advice, 1
-                )
-            }
+                region.assign_advice_from_instance(|| "upper", cfg.instance, 2, cfg.diff_a, 0)
+            },
         )?;
 
-        // lower < secret
-        let _ = lt_chip.assign(
-            layouter.namespace(|| "lower < secret"),
-            lower_cell.clone(),
-            secret_cell.clone(),
+        // secret - lower  (proves lower <= secret when the result is N wide)
+        let secret_minus_lower =
+            self.assign_diff(&cfg, layouter.namespace(|| "secret - lower"), &secret_cell, &lower_cell)?;
+        cfg.range_check.range_check(
+            layouter.namespace(|| "range-check secret - lower"),
+            secret_minus_lower,
+            N,
         )?;
 
-        // secret < upper
-        let _ = lt_chip.assign(
-            layouter.namespace(|| "secret < upper"),
-            secret_cell,
-            upper_cell,
+        // upper - secret  (proves secret <= upper when the result is N wide)
+        let upper_minus_secret =
+            self.assign_diff(&cfg, layouter.namespace(|| "upper - secret"), &upper_cell, &secret_cell)?;
+        cfg.range_check.range_check(
+            layouter.namespace(|| "range-check upper - secret"),
+            upper_minus_secret,
+            N,
         )?;
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+impl<S, const WIDTH: usize, const RATE: usize, const L: usize, const N: usize>
+    RangeCommitCircuit<S, WIDTH, RATE, L, N>
+where
+    S: Spec<Fp, WIDTH, RATE> + Clone,
+{
+    /// Compile-time guard for the `2 <= L <= RATE` invariant documented on
+    /// the struct (`L` hashes `secret` and `r` plus `L - 2` extras, and the
+    /// sponge absorbs all `L` inputs in one permutation). Left unenforced,
+    /// `L < 2` underflows `self.extra.len() == L - 2` and `L > RATE`
+    /// indexes `cfg.poseidon.message[2 + i]` out of bounds -- both would
+    /// surface as unrelated arithmetic/index panics instead of naming the
+    /// actual misuse. Referenced from `configure` so it's checked for every
+    /// monomorphization, not just the ones that happen to get synthesized.
+    const L_IN_RANGE: () = assert!(L >= 2 && L <= RATE, "RangeCommitCircuit requires 2 <= L <= RATE");
+
+    /// Builds a witnessed circuit for `secret` bounded by `[lower, upper]`
+    /// together with the `[commitment, lower, upper]` instance it proves
+    /// against. Samples a fresh blinding trapdoor `r`, so the same `secret`
+    /// never yields the same commitment twice, and derives the off-circuit
+    /// commitment the same way `synthesize` derives the in-circuit one.
+    ///
+    /// Every prover in this crate (the CLI demo, the batch tests, the
+    /// benches) should build its witness through this rather than
+    /// re-deriving the Poseidon hash by hand -- three independent copies of
+    /// that wiring is exactly how an off-circuit commitment quietly stops
+    /// matching what the circuit proves.
+    pub fn witness(secret: u64, lower: u64, upper: u64, extra: Vec<Fp>) -> (Self, Vec<Vec<Fp>>) {
+        let r = Fp::random(OsRng);
+        let secret = Fp::from(secret);
+        let lower = Fp::from(lower);
+        let upper = Fp::from(upper);
+
+        let mut inputs = vec![secret, r];
+        inputs.extend(extra.iter().copied());
+        let inputs: [Fp; L] = inputs
+            .try_into()
+            .unwrap_or_else(|v: Vec<Fp>| panic!("expected {L} hash inputs, got {}", v.len()));
+        let commitment = PoseidonHash::<Fp, S, ConstantLength<L>, WIDTH, RATE>::init().hash(inputs);
+
+        let circuit = Self {
+            secret: Some(secret),
+            r: Some(r),
+            extra,
+            lower,
+            upper,
+            ..Self::default()
+        };
+        let instance = vec![vec![commitment, lower, upper]];
+
+        (circuit, instance)
+    }
+
+    /// Witnesses `a - b` and constrains it with the `s_diff` gate, returning
+    /// the `diff_out` cell so the caller can feed it into a range check.
+    fn assign_diff(
+        &self,
+        cfg: &RangeCommitConfig<WIDTH, RATE>,
+        mut layouter: impl Layouter<Fp>,
+        a: &AssignedCell<Fp, Fp>,
+        b: &AssignedCell<Fp, Fp>,
+    ) -> Result<AssignedCell<Fp, Fp>, Error> {
+        layouter.assign_region(
+            || "a - b",
+            |mut region| {
+                cfg.s_diff.enable(&mut region, 0)?;
+                a.copy_advice(|| "a", &mut region, cfg.diff_a, 0)?;
+                b.copy_advice(|| "b", &mut region, cfg.diff_b, 0)?;
+                let diff = a.value().copied() - b.value().copied();
+                region.assign_advice(|| "a - b", cfg.diff_out, 0, || diff)
+            },
+        )
+    }
+}